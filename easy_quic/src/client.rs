@@ -1,9 +1,11 @@
-use anyhow::Result;
-use quinn::{ClientConfig, Endpoint};
-use rustls::pki_types::ServerName;
+use crate::config::ClientConfig as EasyQuicClientConfig;
+use crate::message::MessageStream;
+use crate::session::{self, SessionStore};
+use crate::tls;
+use anyhow::{anyhow, Result};
+use quinn::{ClientConfig as QuinnClientConfig, Endpoint};
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::io::AsyncWriteExt;
 use tracing::info;
 
 pub struct QuicClient {
@@ -12,49 +14,114 @@ pub struct QuicClient {
 
 impl QuicClient {
     pub async fn new(bind_addr: SocketAddr) -> Result<Self> {
-        let client_config = configure_client();
-        let mut endpoint = Endpoint::client(bind_addr)?;
+        let config = EasyQuicClientConfig {
+            bind_addr,
+            ..EasyQuicClientConfig::default()
+        };
+        Self::with_config(config).await
+    }
+
+    pub async fn with_config(config: EasyQuicClientConfig) -> Result<Self> {
+        Self::with_session_store(config, session::in_memory_session_store()).await
+    }
+
+    /// Like `with_config`, but with an explicit `SessionStore` for TLS
+    /// session tickets instead of the default in-memory cache. Reusing the
+    /// same store across clients/restarts is what lets `connect_0rtt`
+    /// actually find a ticket to resume from.
+    pub async fn with_session_store(
+        config: EasyQuicClientConfig,
+        session_store: Arc<dyn SessionStore>,
+    ) -> Result<Self> {
+        let client_config = configure_client(&config, session_store)?;
+        let mut endpoint = Endpoint::client(config.bind_addr)?;
         endpoint.set_default_client_config(client_config);
-        
+
         info!("QUIC client initialized on {}", endpoint.local_addr()?);
-        
+
         Ok(Self { endpoint })
     }
-    
+
     pub async fn connect(&self, server_addr: SocketAddr, server_name: &str) -> Result<ClientConnection> {
         info!("Connecting to {} ({})", server_addr, server_name);
-        
+
         let connection = self.endpoint
             .connect(server_addr, server_name)?
             .await?;
-            
+
         info!("Connected to server: {}", connection.remote_address());
-        
-        Ok(ClientConnection { connection })
+
+        Ok(ClientConnection { connection, endpoint: self.endpoint.clone() })
+    }
+
+    /// Connects using a stored session ticket if one is available, so the
+    /// handshake can resume the earlier session in 0-RTT. Returns whether the
+    /// server accepted 0-RTT. The connection is returned only after the
+    /// handshake has completed, so no application data is sent through it as
+    /// 0-RTT early data.
+    pub async fn connect_0rtt(&self, server_addr: SocketAddr, server_name: &str) -> Result<(ClientConnection, bool)> {
+        info!("Connecting to {} ({}) with 0-RTT", server_addr, server_name);
+
+        let connecting = self.endpoint.connect(server_addr, server_name)?;
+        match connecting.into_0rtt() {
+            Ok((connection, accepted)) => {
+                let accepted = accepted.await;
+                info!(
+                    "Connected to server: {} (0-RTT accepted: {})",
+                    connection.remote_address(),
+                    accepted
+                );
+                Ok((
+                    ClientConnection { connection, endpoint: self.endpoint.clone() },
+                    accepted,
+                ))
+            }
+            Err(connecting) => {
+                let connection = connecting.await?;
+                info!(
+                    "Connected to server: {} (no 0-RTT ticket available)",
+                    connection.remote_address()
+                );
+                Ok((
+                    ClientConnection { connection, endpoint: self.endpoint.clone() },
+                    false,
+                ))
+            }
+        }
     }
 }
 
 pub struct ClientConnection {
     connection: quinn::Connection,
+    endpoint: Endpoint,
 }
 
 impl ClientConnection {
+    /// Opens a fresh bi-stream framed for typed message exchange. Prefer
+    /// this over `send_message` when sending more than one message, since it
+    /// lets many frames ride the same stream instead of reopening one per
+    /// message.
+    pub async fn open_message_stream(&self) -> Result<MessageStream> {
+        let (send, recv) = self.connection.open_bi().await?;
+        Ok(MessageStream::new(send, recv))
+    }
+
     pub async fn send_message(&self, message: &str) -> Result<String> {
         info!("Sending message: {}", message);
-        
-        let (mut send, mut recv) = self.connection.open_bi().await?;
-        
-        send.write_all(message.as_bytes()).await?;
-        send.finish()?;
-        
-        let response = recv.read_to_end(64 * 1024).await?;
-        
-        let response_str = String::from_utf8(response)?;
-        info!("Received response: {}", response_str);
-        
-        Ok(response_str)
+
+        let mut stream = self.open_message_stream().await?;
+        stream.send_frame(&message.to_string()).await?;
+        stream.finish_send()?;
+
+        let response: String = stream
+            .next_frame()
+            .await?
+            .ok_or_else(|| anyhow!("connection closed before a response was received"))?;
+        info!("Received response: {}", response);
+
+        Ok(response)
     }
-    
+
     pub async fn close(&self) {
         self.connection.close(0u32.into(), b"done");
         info!("Connection closed");
@@ -63,61 +130,67 @@ impl ClientConnection {
     pub fn remote_address(&self) -> SocketAddr {
         self.connection.remote_address()
     }
-}
 
-fn configure_client() -> ClientConfig {
-    let crypto = rustls::ClientConfig::builder()
-        .dangerous()
-        .with_custom_certificate_verifier(Arc::new(SkipServerVerification))
-        .with_no_client_auth();
-    
-    ClientConfig::with_crypto(Arc::new(quinn::crypto::rustls::QuicClientConfig::try_from(crypto).unwrap()))
-}
+    /// Drives a TCP/UDP port forward over this connection until it errors or
+    /// the connection closes. See [`crate::forward`] for the listener/dialer
+    /// roles each [`Forward::direction`](crate::forward::Forward) implies.
+    pub async fn run_forward(&self, forward: &crate::forward::Forward) -> Result<()> {
+        crate::forward::run_as_client(&self.connection, forward).await
+    }
 
-#[derive(Debug)]
-struct SkipServerVerification;
-
-impl rustls::client::danger::ServerCertVerifier for SkipServerVerification {
-    fn verify_server_cert(
-        &self,
-        _end_entity: &rustls::pki_types::CertificateDer<'_>,
-        _intermediates: &[rustls::pki_types::CertificateDer<'_>],
-        _server_name: &ServerName<'_>,
-        _ocsp_response: &[u8],
-        _now: rustls::pki_types::UnixTime,
-    ) -> Result<rustls::client::danger::ServerCertVerified, rustls::Error> {
-        Ok(rustls::client::danger::ServerCertVerified::assertion())
+    /// Runs a throughput/integrity self-test against a peer running
+    /// `transfer::serve`, opening `params.concurrent_streams` concurrent
+    /// streams and aggregating the results.
+    pub async fn run_transfer_test(&self, params: crate::transfer::TransferParams) -> Result<crate::transfer::TransferReport> {
+        crate::transfer::run_client(&self.connection, params).await
     }
-    
-    fn verify_tls12_signature(
-        &self,
-        _message: &[u8],
-        _cert: &rustls::pki_types::CertificateDer<'_>,
-        _dss: &rustls::DigitallySignedStruct,
-    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
-        Ok(rustls::client::danger::HandshakeSignatureValid::assertion())
+
+    /// Migrates this connection to a new local address (e.g. after switching
+    /// network interfaces), without reconnecting. QUIC identifies connections
+    /// by connection ID rather than socket tuple, so the peer recognizes the
+    /// new path automatically.
+    pub fn rebind(&self, new_local_addr: SocketAddr) -> Result<()> {
+        let socket = std::net::UdpSocket::bind(new_local_addr)?;
+        self.endpoint.rebind(socket)?;
+        info!("Rebound to {}", new_local_addr);
+        Ok(())
     }
-    
-    fn verify_tls13_signature(
-        &self,
-        _message: &[u8],
-        _cert: &rustls::pki_types::CertificateDer<'_>,
-        _dss: &rustls::DigitallySignedStruct,
-    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
-        Ok(rustls::client::danger::HandshakeSignatureValid::assertion())
+
+    /// Sends an unreliable, unordered QUIC datagram. Useful for
+    /// latency-sensitive payloads (presence pings, game state) that would
+    /// rather be dropped than delayed behind stream flow control.
+    pub fn send_datagram(&self, data: &[u8]) -> Result<()> {
+        self.connection.send_datagram(bytes::Bytes::copy_from_slice(data))?;
+        Ok(())
     }
-    
-    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
-        vec![
-            rustls::SignatureScheme::RSA_PKCS1_SHA256,
-            rustls::SignatureScheme::ECDSA_NISTP256_SHA256,
-            rustls::SignatureScheme::RSA_PKCS1_SHA384,
-            rustls::SignatureScheme::ECDSA_NISTP384_SHA384,
-            rustls::SignatureScheme::RSA_PKCS1_SHA512,
-            rustls::SignatureScheme::RSA_PSS_SHA256,
-            rustls::SignatureScheme::RSA_PSS_SHA384,
-            rustls::SignatureScheme::RSA_PSS_SHA512,
-            rustls::SignatureScheme::ED25519,
-        ]
+
+    /// Waits for the next unreliable datagram sent by the peer.
+    pub async fn recv_datagram(&self) -> Result<bytes::Bytes> {
+        Ok(self.connection.read_datagram().await?)
+    }
+
+    /// The largest datagram payload the peer will currently accept, or
+    /// `None` if datagrams aren't supported on this connection.
+    pub fn max_datagram_size(&self) -> Option<usize> {
+        self.connection.max_datagram_size()
     }
+}
+
+fn configure_client(
+    config: &EasyQuicClientConfig,
+    session_store: Arc<dyn SessionStore>,
+) -> Result<QuinnClientConfig> {
+    let verifier = tls::build_client_verifier(config)?;
+    let mut crypto = rustls::ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(verifier)
+        .with_no_client_auth();
+    crypto.resumption = rustls::client::Resumption::store(session_store);
+    crypto.enable_early_data = true;
+
+    let mut client_config = QuinnClientConfig::with_crypto(Arc::new(
+        quinn::crypto::rustls::QuicClientConfig::try_from(crypto)?,
+    ));
+    client_config.transport_config(Arc::new(config.build_transport_config()));
+    Ok(client_config)
 }
\ No newline at end of file
@@ -1,11 +1,59 @@
+use quinn::congestion::{self, ControllerFactory};
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Idle timeout used when a configured `keep_alive_interval_secs` is so large
+/// that the derived idle timeout overflows quinn's `IdleTimeout`.
+const DEFAULT_MAX_IDLE_TIMEOUT_SECS: u64 = 300;
+
+/// Congestion control algorithm to use for a connection's transport config.
+///
+/// `Cubic` matches quinn's own default and is the safest general-purpose
+/// choice; `Bbr` is worth opting into on lossy or high bandwidth-delay-product
+/// links where loss-based controllers under-utilize the path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CongestionController {
+    NewReno,
+    Cubic,
+    Bbr,
+}
+
+impl Default for CongestionController {
+    fn default() -> Self {
+        CongestionController::Cubic
+    }
+}
+
+impl CongestionController {
+    fn factory(self) -> Arc<dyn ControllerFactory + Send + Sync + 'static> {
+        match self {
+            CongestionController::NewReno => Arc::new(congestion::NewRenoConfig::default()),
+            CongestionController::Cubic => Arc::new(congestion::CubicConfig::default()),
+            CongestionController::Bbr => Arc::new(congestion::BbrConfig::default()),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
     pub bind_addr: SocketAddr,
     pub max_concurrent_streams: u32,
     pub keep_alive_interval_secs: u64,
+    pub congestion_controller: CongestionController,
+    /// PEM-encoded certificate chain to present to clients. When absent, a
+    /// self-signed cert covering `san_names` is generated on the fly.
+    pub cert_path: Option<PathBuf>,
+    /// PEM-encoded private key matching `cert_path`. Must be set exactly
+    /// when `cert_path` is set; setting only one of the two is an error.
+    pub key_path: Option<PathBuf>,
+    /// DNS names and IP addresses to include as SANs when generating a
+    /// self-signed certificate, so it matches the address clients dial.
+    pub san_names: Vec<String>,
+    /// Bytes of buffer to reserve for incoming unreliable datagrams.
+    pub datagram_receive_buffer_size: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,7 +61,19 @@ pub struct ClientConfig {
     pub bind_addr: SocketAddr,
     pub server_addr: SocketAddr,
     pub server_name: String,
+    pub max_concurrent_streams: u32,
     pub keep_alive_interval_secs: u64,
+    pub congestion_controller: CongestionController,
+    /// Skip server certificate verification entirely. Dangerous: only meant
+    /// for local development against a throwaway self-signed cert.
+    pub insecure_skip_verify: bool,
+    /// Path to a PEM CA bundle to trust instead of the system roots.
+    pub ca_cert_path: Option<PathBuf>,
+    /// Hex-encoded SHA-256 fingerprint of a specific server certificate to
+    /// pin, bypassing chain-of-trust validation.
+    pub pinned_cert_fingerprint: Option<String>,
+    /// Bytes of buffer to reserve for incoming unreliable datagrams.
+    pub datagram_receive_buffer_size: usize,
 }
 
 impl Default for ServerConfig {
@@ -22,6 +82,11 @@ impl Default for ServerConfig {
             bind_addr: "127.0.0.1:4433".parse().unwrap(),
             max_concurrent_streams: 100,
             keep_alive_interval_secs: 5,
+            congestion_controller: CongestionController::default(),
+            cert_path: None,
+            key_path: None,
+            san_names: vec!["localhost".to_string()],
+            datagram_receive_buffer_size: 1024 * 1024,
         }
     }
 }
@@ -32,7 +97,73 @@ impl Default for ClientConfig {
             bind_addr: "127.0.0.1:0".parse().unwrap(),
             server_addr: "127.0.0.1:4433".parse().unwrap(),
             server_name: "localhost".to_string(),
+            max_concurrent_streams: 100,
             keep_alive_interval_secs: 5,
+            congestion_controller: CongestionController::default(),
+            insecure_skip_verify: false,
+            ca_cert_path: None,
+            pinned_cert_fingerprint: None,
+            datagram_receive_buffer_size: 1024 * 1024,
         }
     }
-}
\ No newline at end of file
+}
+
+impl ServerConfig {
+    /// Builds a `quinn::TransportConfig` reflecting this config's stream,
+    /// keep-alive, congestion control and datagram settings.
+    pub(crate) fn build_transport_config(&self) -> quinn::TransportConfig {
+        build_transport_config(
+            self.max_concurrent_streams,
+            self.keep_alive_interval_secs,
+            self.congestion_controller,
+            self.datagram_receive_buffer_size,
+        )
+    }
+}
+
+impl ClientConfig {
+    /// Builds a `quinn::TransportConfig` reflecting this config's stream,
+    /// keep-alive, congestion control and datagram settings.
+    pub(crate) fn build_transport_config(&self) -> quinn::TransportConfig {
+        build_transport_config(
+            self.max_concurrent_streams,
+            self.keep_alive_interval_secs,
+            self.congestion_controller,
+            self.datagram_receive_buffer_size,
+        )
+    }
+}
+
+fn build_transport_config(
+    max_concurrent_streams: u32,
+    keep_alive_interval_secs: u64,
+    congestion_controller: CongestionController,
+    datagram_receive_buffer_size: usize,
+) -> quinn::TransportConfig {
+    // A zero interval isn't a meaningful "keep alive every 0 seconds"; clamp
+    // to 1s so both the keep-alive and the derived idle timeout stay
+    // well-defined instead of degenerating to zero.
+    let keep_alive_interval_secs = keep_alive_interval_secs.max(1);
+    let idle_timeout_secs = keep_alive_interval_secs.saturating_mul(4);
+
+    // `idle_timeout_secs` is derived from a public u64 config field and can be
+    // far larger than quinn's IdleTimeout (a VarInt of milliseconds) accepts.
+    // Fall back to a sane default rather than letting the conversion panic.
+    let idle_timeout = Duration::from_secs(idle_timeout_secs)
+        .try_into()
+        .unwrap_or_else(|_| {
+            Duration::from_secs(DEFAULT_MAX_IDLE_TIMEOUT_SECS)
+                .try_into()
+                .expect("default idle timeout must fit in an IdleTimeout")
+        });
+
+    let mut transport = quinn::TransportConfig::default();
+    transport
+        .max_concurrent_bidi_streams(max_concurrent_streams.into())
+        .max_concurrent_uni_streams(max_concurrent_streams.into())
+        .keep_alive_interval(Some(Duration::from_secs(keep_alive_interval_secs)))
+        .max_idle_timeout(Some(idle_timeout))
+        .congestion_controller_factory(congestion_controller.factory())
+        .datagram_receive_buffer_size(Some(datagram_receive_buffer_size));
+    transport
+}
@@ -0,0 +1,295 @@
+//! Generic TCP/UDP port forwarding over a QUIC connection, analogous to
+//! SSH's `-L`/`-R`. One side of the connection plays the "listener" role
+//! (accepts raw L4 traffic and opens a QUIC stream per flow, announcing the
+//! dial target in a small header), the other plays the "dialer" role
+//! (accepts QUIC streams, reads the header, and connects out to the target).
+//! A [`Direction::LocalToRemote`] forward makes the client the listener and
+//! the server the dialer; [`Direction::RemoteToLocal`] inverts both roles.
+
+use crate::framing;
+use anyhow::{anyhow, Result};
+use quinn::{Connection, RecvStream, SendStream};
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{copy_bidirectional, AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Listen locally, forward each connection/datagram to the peer's target.
+    LocalToRemote,
+    /// Peer listens remotely, forward each connection/datagram to our target.
+    RemoteToLocal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+#[derive(Debug, Clone)]
+pub struct Forward {
+    pub direction: Direction,
+    pub protocol: Protocol,
+    pub bind: SocketAddr,
+    pub target: SocketAddr,
+}
+
+/// Drives `forward` from the client side of `connection`: client listens for
+/// `LocalToRemote`, client dials for `RemoteToLocal`.
+pub async fn run_as_client(connection: &Connection, forward: &Forward) -> Result<()> {
+    match forward.direction {
+        Direction::LocalToRemote => run_listener(connection, forward.protocol, forward.bind, forward.target).await,
+        Direction::RemoteToLocal => run_dialer(connection, forward.protocol).await,
+    }
+}
+
+/// Drives `forward` from the server side of `connection`: server dials for
+/// `LocalToRemote`, server listens for `RemoteToLocal`.
+pub async fn run_as_server(connection: &Connection, forward: &Forward) -> Result<()> {
+    match forward.direction {
+        Direction::LocalToRemote => run_dialer(connection, forward.protocol).await,
+        Direction::RemoteToLocal => run_listener(connection, forward.protocol, forward.bind, forward.target).await,
+    }
+}
+
+async fn run_listener(
+    connection: &Connection,
+    protocol: Protocol,
+    bind: SocketAddr,
+    target: SocketAddr,
+) -> Result<()> {
+    match protocol {
+        Protocol::Tcp => run_tcp_listener(connection, bind, target).await,
+        Protocol::Udp => run_udp_listener(connection, bind, target).await,
+    }
+}
+
+async fn run_dialer(connection: &Connection, protocol: Protocol) -> Result<()> {
+    match protocol {
+        Protocol::Tcp => run_tcp_dialer(connection).await,
+        Protocol::Udp => run_udp_dialer(connection).await,
+    }
+}
+
+async fn run_tcp_listener(connection: &Connection, bind: SocketAddr, target: SocketAddr) -> Result<()> {
+    let listener = TcpListener::bind(bind).await?;
+    info!("Forwarding TCP {} -> {} over QUIC", bind, target);
+
+    loop {
+        let (tcp_stream, peer) = listener.accept().await?;
+        let connection = connection.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = handle_tcp_listener_conn(&connection, tcp_stream, target).await {
+                error!("TCP forward from {} failed: {}", peer, e);
+            }
+        });
+    }
+}
+
+async fn handle_tcp_listener_conn(connection: &Connection, mut tcp_stream: TcpStream, target: SocketAddr) -> Result<()> {
+    let (send, recv) = connection.open_bi().await?;
+    let mut quic_stream = BiStream { send, recv };
+
+    write_frame(&mut quic_stream.send, target.to_string().as_bytes()).await?;
+    copy_bidirectional(&mut tcp_stream, &mut quic_stream).await?;
+    Ok(())
+}
+
+async fn run_tcp_dialer(connection: &Connection) -> Result<()> {
+    loop {
+        let (send, recv) = connection.accept_bi().await?;
+        tokio::spawn(async move {
+            if let Err(e) = handle_tcp_dialer_stream(send, recv).await {
+                error!("TCP forward dial failed: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle_tcp_dialer_stream(send: SendStream, mut recv: RecvStream) -> Result<()> {
+    let target = read_target_header(&mut recv).await?;
+    let mut tcp_stream = TcpStream::connect(target).await?;
+    let mut quic_stream = BiStream { send, recv };
+    copy_bidirectional(&mut tcp_stream, &mut quic_stream).await?;
+    Ok(())
+}
+
+async fn run_udp_listener(connection: &Connection, bind: SocketAddr, target: SocketAddr) -> Result<()> {
+    let socket = std::sync::Arc::new(UdpSocket::bind(bind).await?);
+    info!("Forwarding UDP {} -> {} over QUIC", bind, target);
+
+    let mut flows: HashMap<SocketAddr, mpsc::UnboundedSender<Vec<u8>>> = HashMap::new();
+    let mut buf = vec![0u8; 64 * 1024];
+
+    loop {
+        let (len, source) = socket.recv_from(&mut buf).await?;
+        let datagram = buf[..len].to_vec();
+
+        let flow_tx = match flows.get(&source) {
+            Some(tx) if !tx.is_closed() => tx.clone(),
+            _ => {
+                let (tx, rx) = mpsc::unbounded_channel();
+                let (mut send, recv) = connection.open_bi().await?;
+                write_frame(&mut send, target.to_string().as_bytes()).await?;
+                spawn_udp_flow(send, recv, rx, socket.clone(), source, Some(source));
+                flows.insert(source, tx.clone());
+                tx
+            }
+        };
+
+        if flow_tx.send(datagram).is_err() {
+            flows.remove(&source);
+        }
+    }
+}
+
+async fn run_udp_dialer(connection: &Connection) -> Result<()> {
+    loop {
+        let (send, mut recv) = connection.accept_bi().await?;
+        let target = read_target_header(&mut recv).await?;
+        let local_socket = std::sync::Arc::new(UdpSocket::bind("0.0.0.0:0").await?);
+        local_socket.connect(target).await?;
+        let (tx, rx) = mpsc::unbounded_channel();
+        spawn_udp_flow(send, recv, rx, local_socket.clone(), target, None);
+
+        // Pump datagrams from the dialed target back into the flow.
+        tokio::spawn(async move {
+            let mut buf = vec![0u8; 64 * 1024];
+            loop {
+                match local_socket.recv(&mut buf).await {
+                    Ok(len) => {
+                        if tx.send(buf[..len].to_vec()).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        warn!("UDP forward target {} stopped responding: {}", target, e);
+                        break;
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Relays a single UDP flow between `socket` and a QUIC stream: datagrams
+/// arriving on `rx` (from the raw socket) are written to `send` as
+/// length-prefixed frames, and frames read from `recv` are written back to
+/// `socket`, either via `send_to(reply_to)` (listener side, one socket
+/// shared across flows, so replies must be addressed back to `reply_to`) or
+/// `send` (dialer side, one connected socket per flow, `send_to` is `None`).
+fn spawn_udp_flow(
+    mut send: SendStream,
+    mut recv: RecvStream,
+    mut rx: mpsc::UnboundedReceiver<Vec<u8>>,
+    socket: std::sync::Arc<UdpSocket>,
+    reply_to: SocketAddr,
+    send_to: Option<SocketAddr>,
+) {
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                datagram = rx.recv() => {
+                    match datagram {
+                        Some(datagram) => {
+                            if let Err(e) = write_frame(&mut send, &datagram).await {
+                                error!("UDP forward write failed: {}", e);
+                                break;
+                            }
+                        }
+                        None => break,
+                    }
+                }
+                frame = read_frame(&mut recv) => {
+                    match frame {
+                        Ok(Some(payload)) => {
+                            let result = match send_to {
+                                Some(target) => socket.send_to(&payload, target).await,
+                                None => socket.send(&payload).await,
+                            };
+                            if let Err(e) = result {
+                                error!("UDP forward relay to {} failed: {}", reply_to, e);
+                                break;
+                            }
+                        }
+                        Ok(None) => break,
+                        Err(e) => {
+                            error!("UDP forward read failed: {}", e);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
+const MAX_FRAME_LEN: usize = 1024 * 1024;
+
+async fn write_frame<W: AsyncWrite + Unpin>(writer: &mut W, payload: &[u8]) -> Result<()> {
+    framing::write_frame(writer, payload, MAX_FRAME_LEN).await
+}
+
+async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Option<Vec<u8>>> {
+    framing::read_frame(reader, MAX_FRAME_LEN).await
+}
+
+async fn read_target_header<R: AsyncRead + Unpin>(reader: &mut R) -> Result<SocketAddr> {
+    let header = read_frame(reader)
+        .await?
+        .ok_or_else(|| anyhow!("connection closed before forward target header was received"))?;
+    String::from_utf8(header)?
+        .parse()
+        .map_err(|e| anyhow!("invalid forward target address: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn target_header_round_trips_the_announced_address() {
+        let (mut a, mut b) = tokio::io::duplex(4096);
+        let target: SocketAddr = "127.0.0.1:9000".parse().unwrap();
+
+        write_frame(&mut a, target.to_string().as_bytes()).await.unwrap();
+        let received = read_target_header(&mut b).await.unwrap();
+
+        assert_eq!(received, target);
+    }
+}
+
+/// Joins a QUIC bi-stream's send and receive halves into a single type so it
+/// can be driven with `tokio::io::copy_bidirectional` alongside a TCP socket.
+struct BiStream {
+    send: SendStream,
+    recv: RecvStream,
+}
+
+impl AsyncRead for BiStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().recv).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for BiStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().send).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().send).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().send).poll_shutdown(cx)
+    }
+}
@@ -0,0 +1,100 @@
+//! Shared 4-byte-big-endian length-prefix framing, used by both
+//! [`crate::forward`] (raw byte payloads) and [`crate::message`]
+//! (bincode-serialized typed payloads) so the wire format and its
+//! bounds/EOF handling live in exactly one place.
+
+use anyhow::{anyhow, Result};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Writes `payload` to `writer` as one length-prefixed frame. Errors if
+/// `payload` exceeds `max_len`.
+pub(crate) async fn write_frame<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    payload: &[u8],
+    max_len: usize,
+) -> Result<()> {
+    if payload.len() > max_len {
+        return Err(anyhow!(
+            "frame of {} bytes exceeds {} byte limit",
+            payload.len(),
+            max_len
+        ));
+    }
+
+    writer
+        .write_all(&(payload.len() as u32).to_be_bytes())
+        .await?;
+    writer.write_all(payload).await?;
+    Ok(())
+}
+
+/// Reads the next length-prefixed frame from `reader`, or `Ok(None)` if the
+/// peer closed the stream cleanly between frames. Errors if the announced
+/// length exceeds `max_len`.
+pub(crate) async fn read_frame<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    max_len: usize,
+) -> Result<Option<Vec<u8>>> {
+    let mut len_buf = [0u8; 4];
+    let mut filled = 0;
+    while filled < len_buf.len() {
+        let n = reader.read(&mut len_buf[filled..]).await?;
+        if n == 0 {
+            if filled == 0 {
+                return Ok(None);
+            }
+            return Err(anyhow!("stream ended inside a frame length prefix"));
+        }
+        filled += n;
+    }
+
+    let len = u32::from_be_bytes(len_buf) as usize;
+    if len > max_len {
+        return Err(anyhow!("frame of {} bytes exceeds {} byte limit", len, max_len));
+    }
+
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload).await?;
+    Ok(Some(payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn frame_round_trips_through_a_stream() {
+        let (mut a, mut b) = tokio::io::duplex(4096);
+
+        write_frame(&mut a, b"hello", 1024).await.unwrap();
+        let received = read_frame(&mut b, 1024).await.unwrap();
+
+        assert_eq!(received, Some(b"hello".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn read_frame_returns_none_on_clean_eof() {
+        let (a, mut b) = tokio::io::duplex(4096);
+        drop(a);
+
+        let received = read_frame(&mut b, 1024).await.unwrap();
+
+        assert_eq!(received, None);
+    }
+
+    #[tokio::test]
+    async fn write_frame_rejects_payloads_over_the_limit() {
+        let (mut a, _b) = tokio::io::duplex(4096);
+
+        assert!(write_frame(&mut a, &[0u8; 8], 4).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn read_frame_rejects_announced_lengths_over_the_limit() {
+        let (mut a, mut b) = tokio::io::duplex(4096);
+
+        a.write_all(&8u32.to_be_bytes()).await.unwrap();
+
+        assert!(read_frame(&mut b, 4).await.is_err());
+    }
+}
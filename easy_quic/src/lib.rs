@@ -1,7 +1,17 @@
 pub mod client;
 pub mod config;
+mod framing;
+pub mod forward;
+pub mod message;
 pub mod server;
+pub mod session;
+mod tls;
+pub mod transfer;
 
 pub use client::{ClientConnection, QuicClient};
 pub use config::{ClientConfig, ServerConfig};
-pub use server::QuicServer;
\ No newline at end of file
+pub use forward::{Direction, Forward, Protocol};
+pub use message::MessageStream;
+pub use server::QuicServer;
+pub use session::SessionStore;
+pub use transfer::{TransferParams, TransferReport};
\ No newline at end of file
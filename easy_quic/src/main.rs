@@ -1,12 +1,12 @@
 use anyhow::Result;
 use eframe::egui;
+use easy_quic::config::{ClientConfig, ServerConfig};
 use easy_quic::{QuicClient, QuicServer};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 use tokio::runtime::Runtime;
-use tokio::io::AsyncWriteExt;
 
 #[derive(Clone)]
 struct Message {
@@ -88,7 +88,12 @@ fn start_server(port: u16, app_messages: Arc<Mutex<Vec<Message>>>) {
     let rt = Runtime::new().unwrap();
     rt.block_on(async {
         let addr = format!("127.0.0.1:{}", port).parse().unwrap();
-        let server = QuicServer::new(addr).await.unwrap();
+        let server = QuicServer::with_config(ServerConfig {
+            bind_addr: addr,
+            ..ServerConfig::default()
+        })
+        .await
+        .unwrap();
         
         println!("Server started on port {}", port);
         
@@ -100,21 +105,20 @@ fn start_server(port: u16, app_messages: Arc<Mutex<Vec<Message>>>) {
                 tokio::spawn(async move {
                     loop {
                         match connection.accept_bi().await {
-                            Ok((mut send, mut recv)) => {
-                                let buffer = recv.read_to_end(64 * 1024).await.unwrap();
-                                
-                                let message = String::from_utf8(buffer).unwrap();
-                                
-                                if let Ok(mut messages) = app_messages.lock() {
-                                    messages.push(Message {
-                                        text: format!("Peer: {}", message),
-                                        is_sent: false,
-                                    });
+                            Ok((send, recv)) => {
+                                let mut stream = easy_quic::MessageStream::new(send, recv);
+
+                                while let Ok(Some(message)) = stream.next_frame::<String>().await {
+                                    if let Ok(mut messages) = app_messages.lock() {
+                                        messages.push(Message {
+                                            text: format!("Peer: {}", message),
+                                            is_sent: false,
+                                        });
+                                    }
+
+                                    let response = format!("Echo: {}", message);
+                                    stream.send_frame(&response).await.unwrap();
                                 }
-                                
-                                let response = format!("Echo: {}", message);
-                                send.write_all(response.as_bytes()).await.unwrap();
-                                send.finish().unwrap();
                             }
                             Err(_) => break,
                         }
@@ -132,8 +136,17 @@ fn start_client(server_port: u16, rx: Receiver<String>, app_messages: Arc<Mutex<
         
         let client_addr = "127.0.0.1:0".parse().unwrap();
         let server_addr = format!("127.0.0.1:{}", server_port).parse().unwrap();
-        
-        let client = QuicClient::new(client_addr).await.unwrap();
+
+        // The chat demo's server generates a throwaway self-signed cert, so
+        // there's no CA to validate it against; skip verification explicitly
+        // rather than defaulting to it.
+        let client = QuicClient::with_config(ClientConfig {
+            bind_addr: client_addr,
+            insecure_skip_verify: true,
+            ..ClientConfig::default()
+        })
+        .await
+        .unwrap();
         let connection = client.connect(server_addr, "localhost").await.unwrap();
         
         println!("Client connected to server");
@@ -1,10 +1,13 @@
+use crate::config::ServerConfig as EasyQuicServerConfig;
+use crate::message::MessageStream;
+use crate::tls;
 use anyhow::{anyhow, Result};
-use quinn::{Endpoint, ServerConfig};
-use rcgen::generate_simple_self_signed;
-use rustls::pki_types::{CertificateDer, PrivatePkcs8KeyDer};
+use quinn::{Endpoint, ServerConfig as QuinnServerConfig};
+use rcgen::{CertificateParams, KeyPair, SanType};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use std::net::IpAddr;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::io::AsyncWriteExt;
 use tracing::{error, info};
 
 pub struct QuicServer {
@@ -14,20 +17,28 @@ pub struct QuicServer {
 
 impl QuicServer {
     pub async fn new(addr: SocketAddr) -> Result<Self> {
-        let (cert, key) = generate_self_signed_cert()?;
-        let server_config = configure_server(cert, key)?;
-        
-        let endpoint = Endpoint::server(server_config, addr)?;
+        let config = EasyQuicServerConfig {
+            bind_addr: addr,
+            ..EasyQuicServerConfig::default()
+        };
+        Self::with_config(config).await
+    }
+
+    pub async fn with_config(config: EasyQuicServerConfig) -> Result<Self> {
+        let (chain, key) = resolve_server_cert(&config)?;
+        let server_config = configure_server(chain, key, &config)?;
+
+        let endpoint = Endpoint::server(server_config, config.bind_addr)?;
         let local_addr = endpoint.local_addr()?;
-        
+
         info!("QUIC server listening on {}", local_addr);
-        
+
         Ok(Self {
             endpoint,
             local_addr,
         })
     }
-    
+
     pub fn local_addr(&self) -> SocketAddr {
         self.local_addr
     }
@@ -53,22 +64,17 @@ impl QuicServer {
 
 async fn handle_connection(connection: quinn::Connection) -> Result<()> {
     info!("Handling connection from: {}", connection.remote_address());
-    
+
     loop {
         match connection.accept_bi().await {
-            Ok((mut send, mut recv)) => {
+            Ok((send, recv)) => {
                 info!("Accepted bidirectional stream");
-                
-                let buffer = recv.read_to_end(64 * 1024).await?;
-                
-                let message = String::from_utf8(buffer)?;
-                info!("Received message: {}", message);
-                
-                let response = format!("Echo: {}", message);
-                send.write_all(response.as_bytes()).await?;
-                send.finish()?;
-                
-                info!("Sent response: {}", response);
+
+                tokio::spawn(async move {
+                    if let Err(e) = handle_message_stream(send, recv).await {
+                        error!("Message stream error: {}", e);
+                    }
+                });
             }
             Err(quinn::ConnectionError::ApplicationClosed(_)) => {
                 info!("Connection closed by peer");
@@ -80,28 +86,83 @@ async fn handle_connection(connection: quinn::Connection) -> Result<()> {
             }
         }
     }
-    
+
     Ok(())
 }
 
-fn generate_self_signed_cert() -> Result<(CertificateDer<'static>, PrivatePkcs8KeyDer<'static>)> {
-    let cert = generate_simple_self_signed(vec!["localhost".to_string()])?;
-    let cert_der = cert.cert.der().clone();
-    let key_der = cert.key_pair.serialize_der();
-    
-    Ok((cert_der, key_der.try_into()?))
+/// Echoes every frame received on a stream until the peer finishes sending,
+/// so one stream can carry many request/response pairs.
+async fn handle_message_stream(send: quinn::SendStream, recv: quinn::RecvStream) -> Result<()> {
+    let mut stream = MessageStream::new(send, recv);
+
+    while let Some(message) = stream.next_frame::<String>().await? {
+        info!("Received message: {}", message);
+
+        let response = format!("Echo: {}", message);
+        stream.send_frame(&response).await?;
+
+        info!("Sent response: {}", response);
+    }
+
+    Ok(())
+}
+
+/// Loads the cert chain and key `config` points at, or generates a
+/// self-signed cert covering `config.san_names` if none was configured.
+fn resolve_server_cert(
+    config: &EasyQuicServerConfig,
+) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)> {
+    match (&config.cert_path, &config.key_path) {
+        (Some(cert_path), Some(key_path)) => {
+            let chain = tls::load_cert_chain(cert_path)?;
+            let key = tls::load_private_key(key_path)?;
+            Ok((chain, key))
+        }
+        (None, None) => {
+            let (cert, key) = generate_self_signed_cert(&config.san_names)?;
+            Ok((vec![cert], key))
+        }
+        _ => Err(anyhow!(
+            "ServerConfig.cert_path and key_path must be set together"
+        )),
+    }
+}
+
+fn generate_self_signed_cert(
+    san_names: &[String],
+) -> Result<(CertificateDer<'static>, PrivateKeyDer<'static>)> {
+    let mut params = CertificateParams::new(Vec::<String>::new())?;
+    params.subject_alt_names = san_names
+        .iter()
+        .map(|name| match name.parse::<IpAddr>() {
+            Ok(ip) => Ok(SanType::IpAddress(ip)),
+            Err(_) => Ok(SanType::DnsName(name.as_str().try_into()?)),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let key_pair = KeyPair::generate()?;
+    let cert = params.self_signed(&key_pair)?;
+
+    let cert_der = cert.der().clone();
+    let key_der: PrivateKeyDer<'static> = PrivateKeyDer::Pkcs8(key_pair.serialize_der().into());
+
+    Ok((cert_der, key_der))
 }
 
 fn configure_server(
-    cert: CertificateDer<'static>,
-    key: PrivatePkcs8KeyDer<'static>,
-) -> Result<ServerConfig> {
+    chain: Vec<CertificateDer<'static>>,
+    key: PrivateKeyDer<'static>,
+    config: &EasyQuicServerConfig,
+) -> Result<QuinnServerConfig> {
     let mut crypto = rustls::ServerConfig::builder()
         .with_no_client_auth()
-        .with_single_cert(vec![cert], key.into())?;
-    
+        .with_single_cert(chain, key)?;
+
     crypto.alpn_protocols = vec![b"quic-echo".to_vec()];
-    
-    let server_config = ServerConfig::with_crypto(Arc::new(quinn::crypto::rustls::QuicServerConfig::try_from(crypto)?));
+    // Accept 0-RTT early data from clients resuming a prior session.
+    crypto.max_early_data_size = u32::MAX;
+
+    let mut server_config = QuinnServerConfig::with_crypto(Arc::new(quinn::crypto::rustls::QuicServerConfig::try_from(crypto)?));
+    server_config.transport_config(Arc::new(config.build_transport_config()));
     Ok(server_config)
 }
\ No newline at end of file
@@ -0,0 +1,17 @@
+//! Pluggable storage for TLS session tickets, so a `QuicClient` can attempt
+//! 0-RTT resumption against servers it has previously connected to.
+//!
+//! `rustls::client::ClientSessionStore` is already the right extension
+//! point for this — implement it to persist tickets somewhere durable (e.g.
+//! to disk); `in_memory_session_store` below is the default and only lives
+//! for the process lifetime.
+
+use std::sync::Arc;
+
+pub use rustls::client::ClientSessionStore as SessionStore;
+
+/// Builds the default in-memory `SessionStore` used when a `QuicClient` is
+/// constructed without an explicit one.
+pub fn in_memory_session_store() -> Arc<dyn SessionStore> {
+    Arc::new(rustls::client::ClientSessionMemoryCache::new(32))
+}
@@ -0,0 +1,262 @@
+use crate::config::ClientConfig;
+use anyhow::{anyhow, Context, Result};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Accepts any server certificate without validation.
+///
+/// Only ever constructed when `ClientConfig::insecure_skip_verify` is set
+/// explicitly, so the dangerous path has to be opted into rather than
+/// stumbled into.
+#[derive(Debug)]
+pub(crate) struct SkipServerVerification;
+
+impl ServerCertVerifier for SkipServerVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        all_signature_schemes()
+    }
+}
+
+/// Accepts a server certificate whose SHA-256 fingerprint matches a pinned
+/// value, bypassing chain-of-trust validation entirely. Useful for pinning
+/// to a known leaf/self-signed cert without importing it as a CA.
+#[derive(Debug)]
+pub(crate) struct PinnedCertVerifier {
+    fingerprint: [u8; 32],
+}
+
+impl PinnedCertVerifier {
+    pub(crate) fn new(fingerprint_hex: &str) -> Result<Self> {
+        let bytes = decode_hex_fingerprint(fingerprint_hex)?;
+        let fingerprint: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| anyhow!("pinned cert fingerprint must be a 32-byte SHA-256 digest"))?;
+        Ok(Self { fingerprint })
+    }
+}
+
+impl ServerCertVerifier for PinnedCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let digest = Sha256::digest(end_entity.as_ref());
+        if digest.as_slice() == self.fingerprint {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(
+                "server certificate fingerprint did not match pinned value".to_string(),
+            ))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        all_signature_schemes()
+    }
+}
+
+fn all_signature_schemes() -> Vec<rustls::SignatureScheme> {
+    vec![
+        rustls::SignatureScheme::RSA_PKCS1_SHA256,
+        rustls::SignatureScheme::ECDSA_NISTP256_SHA256,
+        rustls::SignatureScheme::RSA_PKCS1_SHA384,
+        rustls::SignatureScheme::ECDSA_NISTP384_SHA384,
+        rustls::SignatureScheme::RSA_PKCS1_SHA512,
+        rustls::SignatureScheme::RSA_PSS_SHA256,
+        rustls::SignatureScheme::RSA_PSS_SHA384,
+        rustls::SignatureScheme::RSA_PSS_SHA512,
+        rustls::SignatureScheme::ED25519,
+    ]
+}
+
+fn decode_hex_fingerprint(fingerprint: &str) -> Result<Vec<u8>> {
+    let cleaned = fingerprint.trim().replace([':', ' '], "");
+    if cleaned.len() % 2 != 0 {
+        return Err(anyhow!("fingerprint must have an even number of hex digits"));
+    }
+    (0..cleaned.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&cleaned[i..i + 2], 16).context("invalid hex digit in fingerprint")
+        })
+        .collect()
+}
+
+/// Builds the certificate verifier a `QuicClient` should use for `config`:
+/// skip verification if explicitly requested, otherwise pin to a known
+/// fingerprint if one was given, otherwise validate the chain against a CA
+/// bundle (or the system trust store if none was configured).
+pub(crate) fn build_client_verifier(config: &ClientConfig) -> Result<Arc<dyn ServerCertVerifier>> {
+    if config.insecure_skip_verify {
+        return Ok(Arc::new(SkipServerVerification));
+    }
+
+    if let Some(fingerprint) = &config.pinned_cert_fingerprint {
+        return Ok(Arc::new(PinnedCertVerifier::new(fingerprint)?));
+    }
+
+    let roots = load_root_store(config.ca_cert_path.as_deref())?;
+    let verifier = rustls::client::WebPkiServerVerifier::builder(Arc::new(roots))
+        .build()
+        .context("failed to build certificate verifier from trust roots")?;
+    Ok(verifier)
+}
+
+fn load_root_store(ca_cert_path: Option<&Path>) -> Result<rustls::RootCertStore> {
+    let mut roots = rustls::RootCertStore::empty();
+
+    match ca_cert_path {
+        Some(path) => {
+            for cert in load_cert_chain(path)? {
+                roots.add(cert)?;
+            }
+        }
+        None => {
+            for cert in rustls_native_certs::load_native_certs().certs {
+                roots.add(cert)?;
+            }
+        }
+    }
+
+    Ok(roots)
+}
+
+/// Parses a PEM bundle into a certificate chain, for server cert chains and
+/// client CA bundles alike.
+pub(crate) fn load_cert_chain(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let mut reader = BufReader::new(
+        File::open(path).with_context(|| format!("opening PEM bundle {}", path.display()))?,
+    );
+    rustls_pemfile::certs(&mut reader)
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .with_context(|| format!("parsing PEM certificates from {}", path.display()))
+}
+
+/// Parses a single private key out of a PEM file.
+pub(crate) fn load_private_key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    let mut reader = BufReader::new(
+        File::open(path).with_context(|| format!("opening private key {}", path.display()))?,
+    );
+    rustls_pemfile::private_key(&mut reader)
+        .with_context(|| format!("parsing private key from {}", path.display()))?
+        .ok_or_else(|| anyhow!("no private key found in {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_hex_fingerprint_accepts_plain_hex() {
+        let bytes = decode_hex_fingerprint("deadbeef").unwrap();
+        assert_eq!(bytes, vec![0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    #[test]
+    fn decode_hex_fingerprint_ignores_colons_and_whitespace() {
+        let bytes = decode_hex_fingerprint(" DE:AD:BE:EF \n").unwrap();
+        assert_eq!(bytes, vec![0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    #[test]
+    fn decode_hex_fingerprint_rejects_odd_length() {
+        assert!(decode_hex_fingerprint("abc").is_err());
+    }
+
+    #[test]
+    fn decode_hex_fingerprint_rejects_invalid_digits() {
+        assert!(decode_hex_fingerprint("zz").is_err());
+    }
+
+    #[test]
+    fn pinned_cert_verifier_rejects_non_32_byte_fingerprint() {
+        assert!(PinnedCertVerifier::new("deadbeef").is_err());
+    }
+
+    #[test]
+    fn pinned_cert_verifier_accepts_32_byte_fingerprint() {
+        let fingerprint = "00".repeat(32);
+        assert!(PinnedCertVerifier::new(&fingerprint).is_ok());
+    }
+
+    #[test]
+    fn pinned_cert_verifier_matches_cert_digest() {
+        let cert = CertificateDer::from(b"not a real certificate".to_vec());
+        let digest = Sha256::digest(cert.as_ref());
+        let fingerprint_hex = digest.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+
+        let verifier = PinnedCertVerifier::new(&fingerprint_hex).unwrap();
+        let server_name = ServerName::try_from("localhost").unwrap();
+        let result = verifier.verify_server_cert(&cert, &[], &server_name, &[], UnixTime::now());
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn pinned_cert_verifier_rejects_mismatched_cert() {
+        let cert = CertificateDer::from(b"not a real certificate".to_vec());
+        let other_fingerprint = "00".repeat(32);
+
+        let verifier = PinnedCertVerifier::new(&other_fingerprint).unwrap();
+        let server_name = ServerName::try_from("localhost").unwrap();
+        let result = verifier.verify_server_cert(&cert, &[], &server_name, &[], UnixTime::now());
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,215 @@
+//! Throughput/integrity self-test: open several concurrent bi-streams, push
+//! pseudo-random blocks down each with a trailing CRC-32, and have the peer
+//! verify and report back. Exercises multiplexing and gives a real goodput
+//! number instead of guessing from the single-message echo path.
+
+use anyhow::{anyhow, Result};
+use crc::{Crc, CRC_32_ISO_HDLC};
+use quinn::{Connection, RecvStream, SendStream};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tracing::error;
+
+const CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+#[derive(Debug, Clone, Copy)]
+pub struct TransferParams {
+    pub concurrent_streams: usize,
+    pub block_size: usize,
+    pub blocks_per_stream: u64,
+}
+
+impl Default for TransferParams {
+    fn default() -> Self {
+        Self {
+            concurrent_streams: 4,
+            block_size: 64 * 1024,
+            blocks_per_stream: 16,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferStatus {
+    Ok,
+    CrcMismatch,
+}
+
+#[derive(Debug, Clone)]
+pub struct StreamReport {
+    pub stream_index: usize,
+    pub bytes: u64,
+    pub duration: Duration,
+    pub status: TransferStatus,
+}
+
+#[derive(Debug, Clone)]
+pub struct TransferReport {
+    pub total_bytes: u64,
+    pub duration: Duration,
+    pub throughput_mbps: f64,
+    pub streams: Vec<StreamReport>,
+    pub mismatches: usize,
+}
+
+/// Runs `params.concurrent_streams` transfer streams over `connection` and
+/// aggregates the results.
+pub async fn run_client(connection: &Connection, params: TransferParams) -> Result<TransferReport> {
+    let start = Instant::now();
+
+    let handles: Vec<_> = (0..params.concurrent_streams)
+        .map(|stream_index| {
+            let connection = connection.clone();
+            tokio::spawn(async move { run_stream(&connection, stream_index, params).await })
+        })
+        .collect();
+
+    let mut streams = Vec::with_capacity(handles.len());
+    let mut total_bytes = 0u64;
+    let mut mismatches = 0usize;
+    for handle in handles {
+        let report = handle.await??;
+        total_bytes += report.bytes;
+        if report.status == TransferStatus::CrcMismatch {
+            mismatches += 1;
+        }
+        streams.push(report);
+    }
+
+    let duration = start.elapsed();
+    let throughput_mbps = (total_bytes as f64 * 8.0) / duration.as_secs_f64() / 1_000_000.0;
+
+    Ok(TransferReport {
+        total_bytes,
+        duration,
+        throughput_mbps,
+        streams,
+        mismatches,
+    })
+}
+
+async fn run_stream(connection: &Connection, stream_index: usize, params: TransferParams) -> Result<StreamReport> {
+    let (mut send, mut recv) = connection.open_bi().await?;
+    let start = Instant::now();
+
+    let mut digest = CRC32.digest();
+    let mut rng = XorShiftRng::seeded(stream_index as u64);
+    let mut block = vec![0u8; params.block_size];
+
+    for _ in 0..params.blocks_per_stream {
+        rng.fill(&mut block);
+        digest.update(&block);
+        send.write_all(&block).await?;
+    }
+
+    let crc = digest.finalize();
+    send.write_all(&crc.to_be_bytes()).await?;
+    send.finish()?;
+
+    let mut status_buf = [0u8; 1];
+    recv.read_exact(&mut status_buf)
+        .await
+        .map_err(|e| anyhow!("reading transfer status: {}", e))?;
+    let mut count_buf = [0u8; 8];
+    recv.read_exact(&mut count_buf)
+        .await
+        .map_err(|e| anyhow!("reading transfer byte count: {}", e))?;
+
+    let status = match status_buf[0] {
+        0 => TransferStatus::Ok,
+        _ => TransferStatus::CrcMismatch,
+    };
+
+    Ok(StreamReport {
+        stream_index,
+        bytes: u64::from_be_bytes(count_buf),
+        duration: start.elapsed(),
+        status,
+    })
+}
+
+/// Serves transfer-test streams on `connection` until it closes: reads each
+/// stream to completion, verifies the trailing CRC-32, and replies with a
+/// one-byte status plus the received byte count.
+pub async fn serve(connection: &Connection) -> Result<()> {
+    loop {
+        match connection.accept_bi().await {
+            Ok((send, recv)) => {
+                tokio::spawn(async move {
+                    if let Err(e) = handle_stream(send, recv).await {
+                        error!("Transfer test stream failed: {}", e);
+                    }
+                });
+            }
+            Err(quinn::ConnectionError::ApplicationClosed(_)) => break,
+            Err(e) => {
+                error!("Transfer test connection error: {}", e);
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn handle_stream(mut send: SendStream, mut recv: RecvStream) -> Result<()> {
+    let mut digest = CRC32.digest();
+    let mut total = 0u64;
+    let mut buf = vec![0u8; 64 * 1024];
+    // The last 4 bytes of the stream are the CRC footer, not payload, but we
+    // only know we've reached them once the stream ends. Hold back up to 4
+    // unconfirmed bytes and only fold them into the digest once more data
+    // proves they weren't the footer.
+    let mut carry: Vec<u8> = Vec::with_capacity(4);
+
+    while let Some(n) = recv.read(&mut buf).await? {
+        carry.extend_from_slice(&buf[..n]);
+        if carry.len() > 4 {
+            let consume = carry.len() - 4;
+            digest.update(&carry[..consume]);
+            total += consume as u64;
+            carry.drain(..consume);
+        }
+    }
+
+    if carry.len() != 4 {
+        return Err(anyhow!("transfer stream ended without a complete CRC footer"));
+    }
+    let received_crc = u32::from_be_bytes(carry.try_into().unwrap());
+    let computed_crc = digest.finalize();
+
+    let status: u8 = if received_crc == computed_crc { 0 } else { 1 };
+    send.write_all(&[status]).await?;
+    send.write_all(&total.to_be_bytes()).await?;
+    send.finish()?;
+
+    Ok(())
+}
+
+/// Minimal deterministic PRNG for filling transfer blocks with non-trivial
+/// data; not cryptographic, just enough to defeat naive compression and give
+/// every stream a distinct payload.
+struct XorShiftRng {
+    state: u64,
+}
+
+impl XorShiftRng {
+    fn seeded(seed: u64) -> Self {
+        Self { state: seed.wrapping_mul(2_685_821_657_736_338_717).wrapping_add(1) }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+
+    fn fill(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}